@@ -0,0 +1,45 @@
+use clap::Parser;
+
+#[derive(Parser, Debug)]
+pub struct MineArgs {
+    #[arg(
+        long,
+        short,
+        value_name = "CORES_COUNT",
+        help = "The number of cores to dedicate to mining",
+        default_value = "1"
+    )]
+    pub cores: u64,
+
+    #[arg(
+        long,
+        short,
+        value_name = "BUFFER_SECONDS",
+        help = "The number of seconds before the deadline to stop mining and start submitting",
+        default_value = "5"
+    )]
+    pub buffer_time: u64,
+
+    #[arg(
+        long,
+        value_name = "MICROLAMPORTS",
+        help = "Priority fee to pay for the mine transaction, in microlamports. If not provided, a default is sampled from recent network prioritization fees."
+    )]
+    pub priority_fee: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "DIFFICULTY",
+        help = "The difficulty a solution must meet or exceed before the extra priority fee is applied",
+        default_value = "25"
+    )]
+    pub extra_fee_difficulty: u32,
+
+    #[arg(
+        long,
+        value_name = "PERCENT",
+        help = "The percentage to bump the priority fee by when extra_fee_difficulty is met",
+        default_value = "0"
+    )]
+    pub extra_fee_percent: u64,
+}
@@ -1,4 +1,10 @@
-use std::{sync::Arc, sync::RwLock, sync::atomic::{AtomicU64, Ordering}, time::Instant};
+use std::{
+    sync::atomic::{AtomicU32, AtomicU64, Ordering},
+    sync::Arc,
+    time::Instant,
+};
+
+use parking_lot::RwLock;
 
 use colored::*;
 use drillx::{
@@ -11,14 +17,22 @@ use ore_api::{
 };
 use ore_utils::AccountDeserialize;
 use rand::Rng;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::rpc_config::RpcAccountInfoConfig;
 use solana_program::pubkey::Pubkey;
 use solana_rpc_client::spinner;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
 use solana_sdk::signer::Signer;
 use reqwest::Client;
 use serde_json::json;
 use chrono::Utc;
 use std::cell::RefCell;
 use futures::stream::{self, StreamExt};
+use futures::FutureExt;
+use indicatif::{ProgressBar, ProgressStyle};
+use tokio::sync::mpsc::UnboundedReceiver;
 
 use crate::{
     args::MineArgs,
@@ -31,6 +45,200 @@ use crate::{
 
 const DISCORD_WEBHOOK_URL: &str = "xxxxxxxxxxxxxxx"; // Replace with your Discord webhook URL
 
+// RPC calls are flaky enough on a public node that a single dropped request shouldn't
+// knock the miner into a fallback (a bad cutoff, a random bus). These bound how hard we
+// retry before giving up and letting the caller fall back to its default behavior.
+const RPC_MAX_RETRIES: u32 = 5;
+const RPC_RETRY_BASE_DELAY_MS: u64 = 100;
+
+// Retry an async RPC call up to `RPC_MAX_RETRIES` times with a short exponential backoff,
+// returning the last error if every attempt fails.
+async fn with_retries<T, E, F, Fut>(mut op: F) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, E>>,
+{
+    let mut delay_ms = RPC_RETRY_BASE_DELAY_MS;
+    for attempt in 0..RPC_MAX_RETRIES {
+        match op().await {
+            Ok(value) => return Ok(value),
+            Err(err) if attempt + 1 == RPC_MAX_RETRIES => return Err(err),
+            Err(_) => {
+                tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
+                delay_ms *= 2;
+            }
+        }
+    }
+    unreachable!()
+}
+
+// Same backoff, but for helpers that signal failure with `None` (e.g. `get_clock`) instead
+// of a `Result`.
+async fn retry_some<T, F, Fut>(mut op: F) -> Option<T>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Option<T>>,
+{
+    let mut delay_ms = RPC_RETRY_BASE_DELAY_MS;
+    for attempt in 0..RPC_MAX_RETRIES {
+        if let Some(value) = op().await {
+            return Some(value);
+        }
+        if attempt + 1 < RPC_MAX_RETRIES {
+            tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
+            delay_ms *= 2;
+        }
+    }
+    None
+}
+
+// Same backoff again, but for helpers like `get_config`/`get_proof_with_authority` that
+// signal a transient RPC failure by panicking rather than returning `Result`/`Option`. We
+// catch the panic from one attempt and retry before letting a real, final failure propagate.
+// The default panic hook is swapped out for the duration so an expected, retried failure
+// doesn't spam a full panic message (and backtrace) to stderr on every attempt.
+async fn retry_or_panic<T, F, Fut>(mut op: F) -> T
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = T>,
+{
+    let previous_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {}));
+    let result = retry_or_panic_inner(&mut op).await;
+    std::panic::set_hook(previous_hook);
+    match result {
+        Some(value) => value,
+        // The last attempt runs uncaught with the previous hook back in place, so a real
+        // failure panics and is reported as usual.
+        None => op().await,
+    }
+}
+
+// Runs every attempt but the last, catching panics; `None` if all of them panicked.
+async fn retry_or_panic_inner<T, F, Fut>(op: &mut F) -> Option<T>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = T>,
+{
+    let mut delay_ms = RPC_RETRY_BASE_DELAY_MS;
+    for _ in 0..RPC_MAX_RETRIES - 1 {
+        match std::panic::AssertUnwindSafe(op()).catch_unwind().await {
+            Ok(value) => return Some(value),
+            Err(_) => {
+                tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
+                delay_ms *= 2;
+            }
+        }
+    }
+    None
+}
+
+// Solved difficulties rarely clear this; anything higher folds into the top bucket rather
+// than growing the histogram unboundedly.
+const DIFFICULTY_HISTOGRAM_BUCKETS: usize = 64;
+// Hash rate histogram covers up to ~5M H/s in 10k H/s buckets, which comfortably spans a
+// single machine's drillx throughput.
+const HASH_RATE_HISTOGRAM_BUCKETS: usize = 500;
+const HASH_RATE_BUCKET_WIDTH: f64 = 10_000.0;
+
+// Fixed-width histogram with an incremental (Welford-style) running mean, so both recording
+// a sample and reading back a percentile stay O(1) relative to the number of samples seen.
+// The top bucket is open-ended: values that clear it are still counted there for the
+// distribution shape, but `max` tracks the true running maximum so a percentile that lands
+// in that bucket reports the real value instead of collapsing every extreme solve to the
+// same flat ceiling.
+struct Histogram {
+    buckets: Vec<u64>,
+    bucket_width: f64,
+    count: u64,
+    mean: f64,
+    max: f64,
+}
+
+impl Histogram {
+    fn new(num_buckets: usize, bucket_width: f64) -> Self {
+        Self {
+            buckets: vec![0; num_buckets],
+            bucket_width,
+            count: 0,
+            mean: 0.0,
+            max: 0.0,
+        }
+    }
+
+    fn record(&mut self, value: f64) {
+        let bucket = ((value / self.bucket_width) as usize).min(self.buckets.len() - 1);
+        self.buckets[bucket] += 1;
+        self.count += 1;
+        self.mean += (value - self.mean) / self.count as f64;
+        if value > self.max {
+            self.max = value;
+        }
+    }
+
+    // Returns the upper edge of the bucket containing the p-th percentile (0.0..=1.0), or the
+    // true running max if the percentile falls in the open-ended top bucket.
+    fn percentile(&self, p: f64) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        let target = ((self.count as f64) * p).ceil().max(1.0) as u64;
+        let mut cumulative = 0u64;
+        for (i, &bucket_count) in self.buckets.iter().enumerate() {
+            cumulative += bucket_count;
+            if cumulative >= target {
+                if i == self.buckets.len() - 1 {
+                    return self.max;
+                }
+                return (i + 1) as f64 * self.bucket_width;
+            }
+        }
+        self.max
+    }
+}
+
+// Snapshot of the session's solved-difficulty and hash-rate distributions, handed to the
+// Discord webhook and the periodic console summary.
+pub struct StatsSnapshot {
+    pub rounds: u64,
+    pub mean_difficulty: f64,
+    pub difficulty_p50: f64,
+    pub difficulty_p90: f64,
+    pub difficulty_p99: f64,
+    pub mean_hash_rate: f64,
+}
+
+// Accumulates per-round difficulty and hash-rate samples across a mining session.
+struct MiningStats {
+    difficulty: Histogram,
+    hash_rate: Histogram,
+}
+
+impl MiningStats {
+    fn new() -> Self {
+        Self {
+            difficulty: Histogram::new(DIFFICULTY_HISTOGRAM_BUCKETS, 1.0),
+            hash_rate: Histogram::new(HASH_RATE_HISTOGRAM_BUCKETS, HASH_RATE_BUCKET_WIDTH),
+        }
+    }
+
+    fn record(&mut self, difficulty: u32, hash_rate: f64) {
+        self.difficulty.record(difficulty as f64);
+        self.hash_rate.record(hash_rate);
+    }
+
+    fn snapshot(&self) -> StatsSnapshot {
+        StatsSnapshot {
+            rounds: self.difficulty.count,
+            mean_difficulty: self.difficulty.mean,
+            difficulty_p50: self.difficulty.percentile(0.50),
+            difficulty_p90: self.difficulty.percentile(0.90),
+            difficulty_p99: self.difficulty.percentile(0.99),
+            mean_hash_rate: self.hash_rate.mean,
+        }
+    }
+}
+
 impl Miner {
     pub async fn mine(&self, args: MineArgs) {
         // Open account, if needed.
@@ -40,15 +248,20 @@ impl Miner {
         // Check num threads
         self.check_num_cores(args.cores);
 
+        // Subscribe to the signer's proof account over websocket so the loop wakes up the
+        // moment a new challenge lands or a reward is paid out. Falls back to polling below
+        // if the endpoint has no WS support or the subscription drops.
+        let mut proof_updates = self.subscribe_proof_updates(signer.pubkey()).await;
+
         // Start mining loop
         let mut last_hash_at = 0;
         let mut last_balance = 0;
+        let mut stats = MiningStats::new();
+        let mut proof =
+            retry_or_panic(|| get_proof_with_authority(&self.rpc_client, signer.pubkey())).await;
         loop {
             // Fetch proof
-            let config = get_config(&self.rpc_client).await;
-            let proof =
-            get_proof_with_authority(&self.rpc_client, signer.pubkey())
-                    .await;
+            let config = retry_or_panic(|| get_config(&self.rpc_client)).await;
             println!(
                 "\n\nStake: {} ORE\n{}  Multiplier: {:12}x",
                 amount_u64_to_string(proof.balance),
@@ -69,10 +282,24 @@ impl Miner {
             let cutoff_time = self.get_cutoff(proof, args.buffer_time).await;
 
             // Run drillx
-            let solution =
+            let (solution, hash_rate) =
                 Self::find_hash_par(proof, cutoff_time, config.min_difficulty as u32)
                     .await;
 
+            // Track this round's difficulty and hash rate for the session-wide summary
+            stats.record(solution.to_hash().difficulty(), hash_rate);
+            let stats_snapshot = stats.snapshot();
+            println!(
+                "{} {} rounds | difficulty p50/p90/p99 {:.0}/{:.0}/{:.0} (mean {:.1}) | {:.0} H/s mean",
+                "STATS".bold().blue(),
+                stats_snapshot.rounds,
+                stats_snapshot.difficulty_p50,
+                stats_snapshot.difficulty_p90,
+                stats_snapshot.difficulty_p99,
+                stats_snapshot.mean_difficulty,
+                stats_snapshot.mean_hash_rate,
+            );
+
             // Build instruction set
             let mut ixs = vec![ore_api::instruction::auth(proof_pubkey(signer.pubkey()))];
             let mut compute_budget = 500_000;
@@ -89,31 +316,149 @@ impl Miner {
                 solution,
             ));
 
+            // Prepend a priority fee, bumped if we landed a high-difficulty solution
+            let priority_fee = self.priority_fee(&args, solution.to_hash().difficulty()).await;
+            if priority_fee.gt(&0) {
+                ixs.insert(
+                    0,
+                    ComputeBudgetInstruction::set_compute_unit_price(priority_fee),
+                );
+            }
+
             // Submit transaction
             match self.send_and_confirm(&ixs, ComputeBudget::Fixed(compute_budget), false).await {
                 Ok(tx_hash) => {
                     println!("{}", "Transaction confirmed successfully.".bold().green());
                     // Send Discord notification with transaction hash
-                    self.send_discord_webhook(&solution, &tx_hash.to_string(), proof.balance, signer.pubkey().to_string()).await;
+                    self.send_discord_webhook(&solution, &tx_hash.to_string(), proof.balance, signer.pubkey().to_string(), &stats_snapshot).await;
                 },
                 Err(err) => {
                     println!("{}: {}", "ERROR".bold().red(), err);
                 }
             }
+
+            // Wait for the proof account to change before starting the next round, preferring
+            // the websocket push notification over a blind poll.
+            proof = match proof_updates.as_mut() {
+                Some(rx) => match tokio::time::timeout(std::time::Duration::from_secs(65), rx.recv())
+                    .await
+                {
+                    Ok(Some(updated)) => updated,
+                    Ok(None) => {
+                        // The sender task exited, which only happens when the subscription
+                        // itself failed or was dropped. Stop relying on it for the rest of
+                        // the session and fall back to polling.
+                        proof_updates = None;
+                        retry_or_panic(|| get_proof_with_authority(&self.rpc_client, signer.pubkey()))
+                            .await
+                    }
+                    Err(_) => {
+                        // No update within the window. This is expected whenever our own
+                        // submission didn't land (insufficient balance, expired blockhash,
+                        // bus contention, ...) and the challenge never rotated, so the
+                        // subscription may still be perfectly healthy -- keep it armed and
+                        // just poll for this round.
+                        retry_or_panic(|| get_proof_with_authority(&self.rpc_client, signer.pubkey()))
+                            .await
+                    }
+                },
+                None => {
+                    retry_or_panic(|| get_proof_with_authority(&self.rpc_client, signer.pubkey()))
+                        .await
+                }
+            };
         }
     }
 
+    // Subscribe to the signer's proof account over websocket. Returns `None` (rather than
+    // erroring) if the RPC endpoint doesn't expose a pubsub port, so callers can fall back to
+    // polling `get_proof_with_authority` instead.
+    async fn subscribe_proof_updates(&self, authority: Pubkey) -> Option<UnboundedReceiver<Proof>> {
+        let ws_url = self
+            .rpc_client
+            .url()
+            .replacen("https://", "wss://", 1)
+            .replacen("http://", "ws://", 1);
+
+        let pubsub_client = PubsubClient::new(&ws_url).await.ok()?;
+        let proof_address = proof_pubkey(authority);
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            let config = RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(CommitmentConfig::confirmed()),
+                ..Default::default()
+            };
+            let Ok((mut updates, _unsubscribe)) = pubsub_client
+                .account_subscribe(&proof_address, Some(config))
+                .await
+            else {
+                return;
+            };
+
+            while let Some(update) = updates.next().await {
+                let Some(account_data) = update.value.data.decode() else {
+                    continue;
+                };
+                if let Ok(proof) = Proof::try_from_bytes(&account_data) {
+                    if tx.send(proof.clone()).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        Some(rx)
+    }
+
     async fn find_hash_par(
         proof: Proof,
         cutoff_time: u64,
         min_difficulty: u32,
-    ) -> Solution {
-        // Shared state for tracking the best difficulty and total hashes
-        let global_best_difficulty = Arc::new(RwLock::new(0u32));
+    ) -> (Solution, f64) {
+        // Shared state for tracking the best difficulty and total hashes. The best
+        // difficulty is read on every iteration near the cutoff check, so an atomic with
+        // fetch_max avoids serializing all cores behind a lock in the hottest loop.
+        let global_best_difficulty = Arc::new(AtomicU32::new(0));
         let global_total_hashes = Arc::new(AtomicU64::new(0));
         let start_time = Instant::now();
         let num_threads = num_cpus::get(); // Get the number of logical cores
 
+        // Live progress bar, updated by a monitor thread a few times per second so the
+        // operator can see elapsed-vs-cutoff, best difficulty, and hash rate while the
+        // search is running instead of only after it finishes.
+        let progress_bar = ProgressBar::new(cutoff_time);
+        progress_bar.set_style(
+            ProgressStyle::default_bar()
+                .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len}s | best difficulty {msg}")
+                .expect("valid progress bar template")
+                .progress_chars("#>-"),
+        );
+        let monitor_done = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let monitor_handle = {
+            let monitor_done = Arc::clone(&monitor_done);
+            let global_total_hashes = Arc::clone(&global_total_hashes);
+            let global_best_difficulty = Arc::clone(&global_best_difficulty);
+            let progress_bar = progress_bar.clone();
+            std::thread::spawn(move || {
+                let mut last_hashes = 0u64;
+                let mut last_tick = Instant::now();
+                while !monitor_done.load(Ordering::Relaxed) {
+                    std::thread::sleep(std::time::Duration::from_millis(250));
+                    let elapsed = start_time.elapsed();
+                    let total_hashes = global_total_hashes.load(Ordering::Relaxed);
+                    let hash_rate =
+                        (total_hashes - last_hashes) as f64 / last_tick.elapsed().as_secs_f64();
+                    last_hashes = total_hashes;
+                    last_tick = Instant::now();
+                    let best_difficulty = global_best_difficulty.load(Ordering::Relaxed);
+                    progress_bar.set_position(elapsed.as_secs().min(cutoff_time));
+                    progress_bar.set_message(format!("{} ({:.0} H/s)", best_difficulty, hash_rate));
+                }
+            })
+        };
+
         // Adaptive nonce range based on thread performance
         let nonce_step = u64::MAX / num_threads as u64;
         let nonce_ranges: Vec<(u64, u64)> = (0..num_threads)
@@ -160,18 +505,14 @@ impl Miner {
                                         best_difficulty = difficulty;
                                         best_hash = hx;
                                         // Update global best difficulty
-                                        let mut best_diff_lock = global_best_difficulty.write().unwrap();
-                                        if best_difficulty > *best_diff_lock {
-                                            *best_diff_lock = best_difficulty;
-                                        }
+                                        global_best_difficulty.fetch_max(best_difficulty, Ordering::Relaxed);
                                     }
                                 }
                                 global_total_hashes.fetch_add(1, Ordering::Relaxed);
 
                                 // Exit if time has elapsed
                                 if start_time.elapsed().as_secs() >= cutoff_time {
-                                    let best_diff_lock = global_best_difficulty.read().unwrap();
-                                    if *best_diff_lock >= min_difficulty {
+                                    if global_best_difficulty.load(Ordering::Relaxed) >= min_difficulty {
                                         break;
                                     }
                                 }
@@ -202,6 +543,10 @@ impl Miner {
             }
         }
 
+        monitor_done.store(true, Ordering::Relaxed);
+        let _ = monitor_handle.join();
+        progress_bar.finish_and_clear();
+
         let total_hashes = global_total_hashes.load(Ordering::Relaxed);
         let elapsed_time = start_time.elapsed().as_secs_f64();
         let hash_rate = total_hashes as f64 / elapsed_time;
@@ -214,7 +559,34 @@ impl Miner {
             hash_rate,
         );
 
-        Solution::new(best_hash.d, best_nonce.to_le_bytes())
+        (Solution::new(best_hash.d, best_nonce.to_le_bytes()), hash_rate)
+    }
+
+    // Resolve the priority fee (in microlamports) to pay for the mine transaction, scaling it
+    // up when the solved difficulty clears the configured "pay more to land it" threshold.
+    async fn priority_fee(&self, args: &MineArgs, difficulty: u32) -> u64 {
+        let base_fee = match args.priority_fee {
+            Some(fee) => fee,
+            None => self.sample_priority_fee().await,
+        };
+
+        if difficulty >= args.extra_fee_difficulty {
+            base_fee.saturating_add(base_fee.saturating_mul(args.extra_fee_percent) / 100)
+        } else {
+            base_fee
+        }
+    }
+
+    // Sample recent prioritization fees paid on the network so the default tracks live
+    // congestion instead of a hardcoded constant.
+    async fn sample_priority_fee(&self) -> u64 {
+        match with_retries(|| self.rpc_client.get_recent_prioritization_fees(&[])).await {
+            Ok(fees) if !fees.is_empty() => {
+                let sum: u64 = fees.iter().map(|fee| fee.prioritization_fee).sum();
+                sum / fees.len() as u64
+            }
+            _ => 0,
+        }
     }
 
     pub fn check_num_cores(&self, cores: u64) {
@@ -229,7 +601,7 @@ impl Miner {
     }
 
     async fn should_reset(&self, config: Config) -> bool {
-        if let Some(clock) = get_clock(&self.rpc_client).await {
+        if let Some(clock) = retry_some(|| get_clock(&self.rpc_client)).await {
             config
                 .last_reset_at
                 .saturating_add(EPOCH_DURATION)
@@ -241,7 +613,7 @@ impl Miner {
     }
 
     async fn get_cutoff(&self, proof: Proof, buffer_time: u64) -> u64 {
-        if let Some(clock) = get_clock(&self.rpc_client).await {
+        if let Some(clock) = retry_some(|| get_clock(&self.rpc_client)).await {
             proof
                 .last_hash_at
                 .saturating_add(60)
@@ -255,7 +627,9 @@ impl Miner {
 
     async fn find_bus(&self) -> Pubkey {
         // Fetch the bus with the largest balance
-        if let Ok(accounts) = self.rpc_client.get_multiple_accounts(&BUS_ADDRESSES).await {
+        if let Ok(accounts) =
+            with_retries(|| self.rpc_client.get_multiple_accounts(&BUS_ADDRESSES)).await
+        {
             let top_bus = Arc::new(RwLock::new((0u64, BUS_ADDRESSES[0])));
 
             // Process accounts in parallel
@@ -265,7 +639,7 @@ impl Miner {
                     async move {
                         if let Some(account) = account {
                             if let Ok(bus) = Bus::try_from_bytes(&account.data) {
-                                let mut top_bus_lock = top_bus.write().unwrap();
+                                let mut top_bus_lock = top_bus.write();
                                 if bus.rewards > top_bus_lock.0 {
                                     *top_bus_lock = (bus.rewards, BUS_ADDRESSES[bus.id as usize]);
                                 }
@@ -275,7 +649,7 @@ impl Miner {
                 })
                 .await;
 
-            let top_bus_lock = top_bus.read().unwrap();
+            let top_bus_lock = top_bus.read();
             return top_bus_lock.1;
         }
 
@@ -284,7 +658,7 @@ impl Miner {
         BUS_ADDRESSES[i]
     }
 
-    async fn send_discord_webhook(&self, solution: &Solution, tx_hash: &str, stake_balance: u64, wallet_address: String) {
+    async fn send_discord_webhook(&self, solution: &Solution, tx_hash: &str, stake_balance: u64, wallet_address: String, stats: &StatsSnapshot) {
         let client = Client::new();
         let diff = solution.to_hash().difficulty();
         let timestamp = Utc::now().to_rfc3339();
@@ -311,6 +685,19 @@ impl Miner {
                     "name": "Details",
                     "value": format!("[Solscan]({})", tx_url),
                     "inline": true
+                },
+                {
+                    "name": "Session Difficulty (p50/p90/p99)",
+                    "value": format!(
+                        "{:.0}/{:.0}/{:.0} over {} rounds",
+                        stats.difficulty_p50, stats.difficulty_p90, stats.difficulty_p99, stats.rounds
+                    ),
+                    "inline": true
+                },
+                {
+                    "name": "Session Mean H/s",
+                    "value": format!("{:.0}", stats.mean_hash_rate),
+                    "inline": true
                 }
             ],
             "timestamp": timestamp